@@ -0,0 +1,146 @@
+use crate::errors::MerkleError;
+
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+pub fn encode_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+pub fn decode_hex(s: &str) -> Result<Vec<u8>, MerkleError> {
+    if s.len() % 2 != 0 || !s.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return Err(MerkleError::InvalidEncoding());
+    }
+
+    (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&s[i..i + 2], 16).map_err(|_| MerkleError::InvalidEncoding()))
+        .collect()
+}
+
+pub fn encode_base64(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+
+    for chunk in bytes.chunks(3) {
+        let b0 = chunk[0];
+        let b1 = *chunk.get(1).unwrap_or(&0);
+        let b2 = *chunk.get(2).unwrap_or(&0);
+        let n = ((b0 as u32) << 16) | ((b1 as u32) << 8) | (b2 as u32);
+
+        out.push(BASE64_ALPHABET[(n >> 18 & 0x3f) as usize] as char);
+        out.push(BASE64_ALPHABET[(n >> 12 & 0x3f) as usize] as char);
+        out.push(if chunk.len() > 1 {
+            BASE64_ALPHABET[(n >> 6 & 0x3f) as usize] as char
+        } else {
+            '='
+        });
+        out.push(if chunk.len() > 2 {
+            BASE64_ALPHABET[(n & 0x3f) as usize] as char
+        } else {
+            '='
+        });
+    }
+
+    out
+}
+
+pub fn decode_base64(s: &str) -> Result<Vec<u8>, MerkleError> {
+    let bytes = s.as_bytes();
+    if bytes.is_empty() {
+        return Ok(Vec::new());
+    }
+    if bytes.len() % 4 != 0 {
+        return Err(MerkleError::InvalidEncoding());
+    }
+
+    let mut out = Vec::with_capacity(bytes.len() / 4 * 3);
+
+    for chunk in bytes.chunks(4) {
+        let pad = chunk.iter().filter(|&&c| c == b'=').count();
+        if pad > 2 || chunk[..4 - pad].iter().any(|&c| c == b'=') {
+            return Err(MerkleError::InvalidEncoding());
+        }
+
+        let mut n = 0u32;
+        for &c in chunk {
+            n <<= 6;
+            if c != b'=' {
+                n |= base64_char_value(c)?;
+            }
+        }
+
+        out.push((n >> 16) as u8);
+        if pad < 2 {
+            out.push((n >> 8) as u8);
+        }
+        if pad < 1 {
+            out.push(n as u8);
+        }
+    }
+
+    Ok(out)
+}
+
+fn base64_char_value(c: u8) -> Result<u32, MerkleError> {
+    match c {
+        b'A'..=b'Z' => Ok((c - b'A') as u32),
+        b'a'..=b'z' => Ok((c - b'a') as u32 + 26),
+        b'0'..=b'9' => Ok((c - b'0') as u32 + 52),
+        b'+' => Ok(62),
+        b'/' => Ok(63),
+        _ => Err(MerkleError::InvalidEncoding()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hex_round_trip() {
+        let bytes = vec![0, 1, 2, 253, 254, 255];
+        assert_eq!(decode_hex(&encode_hex(&bytes)).unwrap(), bytes);
+    }
+
+    #[test]
+    fn test_hex_rejects_odd_length() {
+        assert!(matches!(
+            decode_hex("abc"),
+            Err(MerkleError::InvalidEncoding())
+        ));
+    }
+
+    #[test]
+    fn test_hex_rejects_non_hex_digits() {
+        assert!(matches!(
+            decode_hex("zz"),
+            Err(MerkleError::InvalidEncoding())
+        ));
+    }
+
+    #[test]
+    fn test_base64_round_trip() {
+        for bytes in [
+            vec![],
+            vec![1],
+            vec![1, 2],
+            vec![1, 2, 3],
+            vec![1, 2, 3, 4],
+            (0u8..=255).collect::<Vec<u8>>(),
+        ] {
+            assert_eq!(decode_base64(&encode_base64(&bytes)).unwrap(), bytes);
+        }
+    }
+
+    #[test]
+    fn test_base64_rejects_malformed_input() {
+        assert!(matches!(
+            decode_base64("not base64!"),
+            Err(MerkleError::InvalidEncoding())
+        ));
+        assert!(matches!(
+            decode_base64("ab=a"),
+            Err(MerkleError::InvalidEncoding())
+        ));
+    }
+}
@@ -9,6 +9,11 @@ pub enum MerkleError {
     InsufficientHeight(usize),
     // Index out of bounds
     IndexOutOfBounds(usize),
+    // Returned when trying to insert a leaf that is empty, since that value
+    // is reserved to represent an unoccupied leaf
+    InvalidLeaf(),
+    // Returned when a hex/base64-encoded proof or root is malformed
+    InvalidEncoding(),
 }
 
 impl fmt::Display for MerkleError {
@@ -23,6 +28,12 @@ impl fmt::Display for MerkleError {
             MerkleError::IndexOutOfBounds(index) => {
                 write!(f, "Querying out of bounds leaf at index {}", index)
             }
+            MerkleError::InvalidLeaf() => {
+                write!(f, "Leaf value is empty, which is reserved for padding")
+            }
+            MerkleError::InvalidEncoding() => {
+                write!(f, "Malformed hex/base64 encoding")
+            }
         }
     }
 }
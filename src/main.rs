@@ -1,13 +1,18 @@
+mod encoding;
 mod errors;
+mod multiproof;
 mod proof;
+mod pruner;
+mod store;
 mod tree;
 
 use crate::errors::MerkleError;
+use crate::store::InMemoryNodeStore;
 use sha2::Sha256;
 use tree::*;
 
 fn main() -> Result<(), MerkleError> {
-    let tree = MerkleTree::<Sha256, 0>::from_data(vec![b"0".to_vec()])?;
+    let tree = MerkleTree::<Sha256, InMemoryNodeStore, 0>::from_data(vec![b"0".to_vec()])?;
 
     println!("{:x?}", tree);
 
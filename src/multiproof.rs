@@ -0,0 +1,185 @@
+use sha2::Digest;
+use std::marker::PhantomData;
+
+use crate::proof::constant_time_eq;
+use crate::tree::{LEAF_PREFIX, NODE_PREFIX};
+
+/// A proof that several leaves, identified by index, belong to the same
+/// tree. `indices` are the sorted, deduplicated leaf positions the proof
+/// covers; `siblings` are the authentication hashes needed to recompute the
+/// root, in the order `MultiProof::verify` consumes them: level by level,
+/// in ascending index order, skipping any sibling that is itself one of the
+/// covered leaves or a node derived from them at that level (since the
+/// verifier can derive it directly).
+#[derive(Clone, Debug)]
+pub struct MultiProof<H: Digest, const HEIGHT: u32> {
+    pub indices: Vec<usize>,
+    pub siblings: Vec<Vec<u8>>,
+    hasher: PhantomData<H>,
+}
+
+impl<H: Digest, const HEIGHT: u32> MultiProof<H, HEIGHT> {
+    pub fn new(indices: Vec<usize>, siblings: Vec<Vec<u8>>) -> Self {
+        Self {
+            indices,
+            siblings,
+            hasher: PhantomData,
+        }
+    }
+
+    pub fn verify(&self, root: &[u8], leaves: &[(usize, &[u8])]) -> bool {
+        if leaves.is_empty() {
+            return false;
+        }
+
+        let mut sorted_leaves = leaves.to_vec();
+        sorted_leaves.sort_unstable_by_key(|(index, _)| *index);
+
+        let sorted_indices: Vec<usize> = sorted_leaves.iter().map(|(index, _)| *index).collect();
+        if sorted_indices != self.indices {
+            return false;
+        }
+
+        let mut current: Vec<(usize, Vec<u8>)> = sorted_leaves
+            .iter()
+            .map(|(index, key)| (*index, Self::hash_leaf(key)))
+            .collect();
+
+        let mut siblings = self.siblings.iter();
+
+        for _ in 0..HEIGHT {
+            let mut next = Vec::with_capacity(current.len());
+            let mut i = 0;
+
+            while i < current.len() {
+                let index = current[i].0;
+                let sibling_index = index ^ 1;
+
+                let parent_hash = if i + 1 < current.len() && current[i + 1].0 == sibling_index {
+                    let parent_hash = if index % 2 == 0 {
+                        Self::hash_pair(&current[i].1, &current[i + 1].1)
+                    } else {
+                        Self::hash_pair(&current[i + 1].1, &current[i].1)
+                    };
+                    i += 2;
+                    parent_hash
+                } else {
+                    let sibling_hash = match siblings.next() {
+                        Some(sibling_hash) => sibling_hash,
+                        None => return false,
+                    };
+                    let parent_hash = if index % 2 == 0 {
+                        Self::hash_pair(&current[i].1, sibling_hash)
+                    } else {
+                        Self::hash_pair(sibling_hash, &current[i].1)
+                    };
+                    i += 1;
+                    parent_hash
+                };
+
+                next.push((index / 2, parent_hash));
+            }
+
+            current = next;
+        }
+
+        if siblings.next().is_some() {
+            return false;
+        }
+
+        match current.as_slice() {
+            [(0, root_hash)] => constant_time_eq(root_hash, root),
+            _ => false,
+        }
+    }
+
+    fn hash_leaf(data: &[u8]) -> Vec<u8> {
+        let mut hasher = H::new();
+        hasher.update([LEAF_PREFIX]);
+        hasher.update(data);
+        hasher.finalize().to_vec()
+    }
+
+    fn hash_pair(left: &[u8], right: &[u8]) -> Vec<u8> {
+        let mut hasher = H::new();
+        hasher.update([NODE_PREFIX]);
+        hasher.update(left);
+        hasher.update(right);
+        hasher.finalize().to_vec()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::store::InMemoryNodeStore;
+    use crate::tree::MerkleTree;
+    use sha2::Sha256;
+
+    const TEST_TREE_HEIGHT: u32 = 3;
+
+    fn sample_leaves() -> Vec<Vec<u8>> {
+        vec![
+            b"apple".to_vec(),
+            b"banana".to_vec(),
+            b"cherry".to_vec(),
+            b"date".to_vec(),
+            b"elderberry".to_vec(),
+        ]
+    }
+
+    #[test]
+    fn test_multiproof_adjacent_indices() {
+        let leaves = sample_leaves();
+        let tree = MerkleTree::<Sha256, InMemoryNodeStore, TEST_TREE_HEIGHT>::from_data(
+            leaves.clone(),
+        )
+        .unwrap();
+        let proof = tree.get_multiproof(&[0, 1]).unwrap();
+
+        let keys: Vec<(usize, &[u8])> = vec![(0, leaves[0].as_ref()), (1, leaves[1].as_ref())];
+        assert!(proof.verify(&tree.root().unwrap(), &keys));
+    }
+
+    #[test]
+    fn test_multiproof_disjoint_indices() {
+        let leaves = sample_leaves();
+        let tree = MerkleTree::<Sha256, InMemoryNodeStore, TEST_TREE_HEIGHT>::from_data(
+            leaves.clone(),
+        )
+        .unwrap();
+        let proof = tree.get_multiproof(&[0, 4]).unwrap();
+
+        let keys: Vec<(usize, &[u8])> = vec![(0, leaves[0].as_ref()), (4, leaves[4].as_ref())];
+        assert!(proof.verify(&tree.root().unwrap(), &keys));
+    }
+
+    #[test]
+    fn test_multiproof_full_tree() {
+        let leaves = vec![vec![0u8; 4]; 2_usize.pow(TEST_TREE_HEIGHT)];
+        let tree = MerkleTree::<Sha256, InMemoryNodeStore, TEST_TREE_HEIGHT>::from_data(
+            leaves.clone(),
+        )
+        .unwrap();
+        let indices: Vec<usize> = (0..leaves.len()).collect();
+        let proof = tree.get_multiproof(&indices).unwrap();
+
+        // Every leaf is covered, so no sibling hashes are needed at all.
+        assert!(proof.siblings.is_empty());
+
+        let keys: Vec<(usize, &[u8])> = indices.iter().map(|&i| (i, leaves[i].as_ref())).collect();
+        assert!(proof.verify(&tree.root().unwrap(), &keys));
+    }
+
+    #[test]
+    fn test_multiproof_rejects_wrong_leaf() {
+        let leaves = sample_leaves();
+        let tree = MerkleTree::<Sha256, InMemoryNodeStore, TEST_TREE_HEIGHT>::from_data(
+            leaves.clone(),
+        )
+        .unwrap();
+        let proof = tree.get_multiproof(&[0, 2]).unwrap();
+
+        let keys: Vec<(usize, &[u8])> = vec![(0, leaves[0].as_ref()), (2, b"wrong".as_ref())];
+        assert!(!proof.verify(&tree.root().unwrap(), &keys));
+    }
+}
@@ -1,6 +1,21 @@
 use sha2::Digest;
 use std::marker::PhantomData;
 
+use crate::encoding;
+use crate::errors::MerkleError;
+use crate::tree::{LEAF_PREFIX, NODE_PREFIX};
+
+/// Compares two byte strings without short-circuiting on the first
+/// mismatch, so a verifier can't learn how many leading bytes of a
+/// forged root happened to match.
+pub(crate) fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+
+    a.iter().zip(b.iter()).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
 #[derive(Clone, Debug)]
 pub struct Proof<H: Digest> {
     pub lemma: Vec<Vec<u8>>,
@@ -22,7 +37,7 @@ impl<H: Digest> Proof<H> {
             return false;
         }
 
-        let mut current_hash = H::digest(key).to_vec();
+        let mut current_hash = Self::hash_leaf(key);
         for (proof_hash, position) in self.lemma.iter().zip(self.path.iter()) {
             current_hash = if *position {
                 Self::hash_pair(&current_hash, &proof_hash)
@@ -31,15 +46,76 @@ impl<H: Digest> Proof<H> {
             };
         }
 
-        if current_hash.iter().eq(root.iter()) {
-            return true;
+        constant_time_eq(&current_hash, root)
+    }
+
+    /// Serializes the lemma and path to bytes: one length-prefix byte
+    /// (the proof depth), followed by one `(hash, path bit)` pair per
+    /// level, so the digest length never has to be guessed on decode.
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(1 + self.lemma.len() * (<H as Digest>::output_size() + 1));
+        bytes.push(self.lemma.len() as u8);
+
+        for (hash, position) in self.lemma.iter().zip(self.path.iter()) {
+            bytes.extend_from_slice(hash);
+            bytes.push(*position as u8);
+        }
+
+        bytes
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Result<Self, MerkleError> {
+        let output_size = <H as Digest>::output_size();
+        let depth = *bytes.first().ok_or(MerkleError::InvalidEncoding())? as usize;
+        if bytes.len() != 1 + depth * (output_size + 1) {
+            return Err(MerkleError::InvalidEncoding());
+        }
+
+        let mut lemma = Vec::with_capacity(depth);
+        let mut path = Vec::with_capacity(depth);
+        let mut offset = 1;
+
+        for _ in 0..depth {
+            lemma.push(bytes[offset..offset + output_size].to_vec());
+            offset += output_size;
+
+            path.push(match bytes[offset] {
+                0 => false,
+                1 => true,
+                _ => return Err(MerkleError::InvalidEncoding()),
+            });
+            offset += 1;
         }
 
-        false
+        Ok(Self::new(lemma, path))
+    }
+
+    pub fn to_hex(&self) -> String {
+        encoding::encode_hex(&self.to_bytes())
+    }
+
+    pub fn from_hex(s: &str) -> Result<Self, MerkleError> {
+        Self::from_bytes(&encoding::decode_hex(s)?)
+    }
+
+    pub fn to_base64(&self) -> String {
+        encoding::encode_base64(&self.to_bytes())
+    }
+
+    pub fn from_base64(s: &str) -> Result<Self, MerkleError> {
+        Self::from_bytes(&encoding::decode_base64(s)?)
+    }
+
+    fn hash_leaf(data: &[u8]) -> Vec<u8> {
+        let mut hasher = H::new();
+        hasher.update([LEAF_PREFIX]);
+        hasher.update(data);
+        hasher.finalize().to_vec()
     }
 
     fn hash_pair(left: &[u8], right: &[u8]) -> Vec<u8> {
         let mut hasher = H::new();
+        hasher.update([NODE_PREFIX]);
         hasher.update(left);
         hasher.update(right);
         hasher.finalize().to_vec()
@@ -55,12 +131,12 @@ mod tests {
         let proof = Proof::<Sha256>::new(
             vec![
                 vec![
-                    75, 245, 18, 47, 52, 69, 84, 197, 59, 222, 46, 187, 140, 210, 183, 227, 209,
-                    96, 10, 214, 49, 195, 133, 165, 215, 204, 226, 60, 119, 133, 69, 154,
+                    0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21,
+                    22, 23, 24, 25, 26, 27, 28, 29, 30, 31,
                 ],
                 vec![
-                    209, 115, 79, 241, 216, 102, 116, 137, 81, 135, 62, 94, 133, 51, 44, 182, 227,
-                    254, 141, 152, 99, 1, 125, 142, 255, 243, 180, 154, 65, 164, 208, 251,
+                    32, 33, 34, 35, 36, 37, 38, 39, 40, 41, 42, 43, 44, 45, 46, 47, 48, 49, 50, 51,
+                    52, 53, 54, 55, 56, 57, 58, 59, 60, 61, 62, 63,
                 ],
             ],
             vec![false, true],
@@ -68,8 +144,8 @@ mod tests {
 
         let key = vec![2];
         let root = vec![
-            26, 92, 39, 190, 48, 181, 74, 43, 196, 41, 15, 137, 20, 110, 178, 79, 251, 236, 25,
-            136, 120, 180, 15, 87, 149, 137, 238, 153, 90, 190, 171, 201,
+            2, 40, 23, 214, 62, 148, 40, 17, 129, 143, 28, 139, 56, 232, 149, 8, 106, 77, 37, 185,
+            2, 69, 177, 152, 71, 177, 123, 158, 200, 78, 217, 186,
         ];
 
         (proof, key, root)
@@ -79,21 +155,18 @@ mod tests {
         let proof = Proof::<Sha256>::new(
             vec![
                 vec![
-                    95, 236, 235, 102, 255, 200, 111, 56, 217, 82, 120, 108, 109, 105, 108, 121,
-                    194, 219, 194, 57, 221, 78, 145, 180, 103, 41, 215, 58, 39, 251, 87, 233,
-                ],
-                vec![
-                    66, 219, 238, 180, 235, 93, 65, 187, 220, 147, 115, 44, 106, 135, 171, 50, 65,
-                    238, 3, 244, 74, 7, 128, 165, 45, 223, 131, 31, 95, 216, 139, 83,
+                    0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21,
+                    22, 23, 24, 25, 26, 27, 28, 29, 30, 31,
                 ],
+                vec![255; 32],
             ],
             vec![false, true],
         );
 
         let key = vec![2];
         let root = vec![
-            26, 92, 39, 190, 48, 181, 74, 43, 196, 41, 15, 137, 20, 110, 178, 79, 251, 236, 25,
-            136, 120, 180, 15, 87, 149, 137, 238, 153, 90, 190, 171, 201,
+            2, 40, 23, 214, 62, 148, 40, 17, 129, 143, 28, 139, 56, 232, 149, 8, 106, 77, 37, 185,
+            2, 69, 177, 152, 71, 177, 123, 158, 200, 78, 217, 186,
         ];
 
         (proof, key, root)
@@ -120,4 +193,50 @@ mod tests {
 
         assert!(!proof.verify(&key, &root));
     }
+
+    #[test]
+    fn test_hex_round_trip() {
+        let (proof, key, root) = generate_correct_proof();
+
+        let decoded = Proof::<Sha256>::from_hex(&proof.to_hex()).unwrap();
+        assert!(decoded.verify(&root, &key));
+    }
+
+    #[test]
+    fn test_base64_round_trip() {
+        let (proof, key, root) = generate_correct_proof();
+
+        let decoded = Proof::<Sha256>::from_base64(&proof.to_base64()).unwrap();
+        assert!(decoded.verify(&root, &key));
+    }
+
+    #[test]
+    fn test_from_hex_rejects_malformed_input() {
+        assert!(matches!(
+            Proof::<Sha256>::from_hex("not hex"),
+            Err(MerkleError::InvalidEncoding())
+        ));
+    }
+
+    #[test]
+    fn test_from_base64_rejects_malformed_input() {
+        assert!(matches!(
+            Proof::<Sha256>::from_base64("not base64!"),
+            Err(MerkleError::InvalidEncoding())
+        ));
+    }
+
+    #[test]
+    fn test_from_hex_rejects_truncated_proof() {
+        let (proof, _, _) = generate_correct_proof();
+        let hex = proof.to_hex();
+        // Drop the last byte (a path bit), leaving a length inconsistent
+        // with the depth prefix.
+        let truncated = &hex[..hex.len() - 2];
+
+        assert!(matches!(
+            Proof::<Sha256>::from_hex(truncated),
+            Err(MerkleError::InvalidEncoding())
+        ));
+    }
 }
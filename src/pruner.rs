@@ -0,0 +1,137 @@
+use crate::store::NodeStore;
+
+/// Reclaims storage for node hashes, leaf hashes included, that are no longer reachable from
+/// any proof over the retained leaves, once a tree has grown past the
+/// leaf count a caller still cares about.
+pub struct MerkleTreePruner;
+
+impl MerkleTreePruner {
+    /// Given a store holding a tree of `total_leaf_count` leaves at
+    /// `height`, drops every node that lies past the authentication path of
+    /// the last leaf below `retained_leaf_count`, returning how many nodes
+    /// were removed. Nodes at lower indices, such as the ancestors of
+    /// retained leaves, are kept.
+    ///
+    /// A node can't simply be dropped once its own index falls past the
+    /// retained boundary: the authentication path of the *last* retained
+    /// leaf may still route through it as a sibling. At each level the
+    /// highest index that must survive is therefore the sibling of that
+    /// leaf's ancestor, i.e. its ancestor index rounded up to an odd
+    /// number; everything past it is unreachable from any retained proof.
+    ///
+    /// The root (index 0 at `level == height`) is never dropped, even when
+    /// `retained_leaf_count` is 0: `MerkleTree::root` has no notion of "the
+    /// root was pruned" and would silently fall back to the empty-tree
+    /// zero hash instead.
+    pub fn prune<S: NodeStore>(
+        store: &mut S,
+        height: u32,
+        total_leaf_count: usize,
+        retained_leaf_count: usize,
+    ) -> usize {
+        let mut removed = 0;
+
+        for level in 0..=height {
+            let occupied = total_leaf_count.div_ceil(2_usize.pow(level));
+
+            let keep_from = if retained_leaf_count == 0 {
+                0
+            } else {
+                let last_retained_index = retained_leaf_count - 1;
+                let ancestor_index = last_retained_index >> level;
+                (ancestor_index | 1) + 1
+            };
+            let keep_from = if level == height {
+                keep_from.max(1)
+            } else {
+                keep_from
+            };
+
+            for index in keep_from..occupied {
+                if store.remove(level, index) {
+                    removed += 1;
+                }
+            }
+        }
+
+        removed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::store::InMemoryNodeStore;
+
+    #[test]
+    fn test_prune_drops_nodes_beyond_retained_leaf_count() {
+        let mut store = InMemoryNodeStore::new();
+        for level in 0..=2u32 {
+            for index in 0..4 {
+                store.put(level, index, vec![level as u8, index as u8]);
+            }
+        }
+
+        let removed = MerkleTreePruner::prune(&mut store, 2, 4, 2);
+
+        // Level 0: indices 2, 3 are unreachable from proving either
+        // retained leaf (0 or 1).
+        // Level 1: index 1 is the parent of leaves 2 and 3, but it's also
+        // the sibling needed to prove leaf 1, so it must survive pruning.
+        // Level 2: the single root always survives.
+        assert_eq!(removed, 2);
+        assert_eq!(store.get(0, 0), Some(vec![0, 0]));
+        assert_eq!(store.get(0, 1), Some(vec![0, 1]));
+        assert_eq!(store.get(0, 2), None);
+        assert_eq!(store.get(0, 3), None);
+        assert_eq!(store.get(1, 0), Some(vec![1, 0]));
+        assert_eq!(store.get(1, 1), Some(vec![1, 1]));
+        assert_eq!(store.get(2, 0), Some(vec![2, 0]));
+    }
+
+    #[test]
+    fn test_prune_keeps_sibling_needed_for_last_retained_leaf() {
+        // Out of 8 leaves, only 5 are retained. Leaf 4's sibling at level 0
+        // is leaf 5 — still needed to prove leaf 4, even though leaf 5
+        // itself falls past the retained count. Leaves 6 and 7 are not
+        // needed by any retained proof and can be dropped.
+        let mut store = InMemoryNodeStore::new();
+        for level in 0..=3u32 {
+            for index in 0..8 {
+                store.put(level, index, vec![level as u8, index as u8]);
+            }
+        }
+
+        let removed = MerkleTreePruner::prune(&mut store, 3, 8, 5);
+
+        assert_eq!(removed, 2);
+        assert!(store.get(0, 5).is_some());
+        assert_eq!(store.get(0, 6), None);
+        assert_eq!(store.get(0, 7), None);
+    }
+
+    #[test]
+    fn test_prune_zero_never_drops_the_root() {
+        let mut store = InMemoryNodeStore::new();
+        for level in 0..=3u32 {
+            for index in 0..8 {
+                store.put(level, index, vec![level as u8, index as u8]);
+            }
+        }
+
+        MerkleTreePruner::prune(&mut store, 3, 8, 0);
+
+        assert_eq!(store.get(3, 0), Some(vec![3, 0]));
+    }
+
+    #[test]
+    fn test_prune_is_idempotent() {
+        let mut store = InMemoryNodeStore::new();
+        for index in 0..4 {
+            store.put(0, index, vec![index as u8]);
+        }
+
+        assert_eq!(MerkleTreePruner::prune(&mut store, 2, 4, 2), 2);
+        assert_eq!(MerkleTreePruner::prune(&mut store, 2, 4, 2), 0);
+    }
+}
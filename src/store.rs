@@ -0,0 +1,64 @@
+use std::collections::HashMap;
+
+/// Backing storage for a [`crate::tree::MerkleTree`]'s internal nodes,
+/// keyed by `(level, index)` — level 0 holds leaf hashes, level `HEIGHT`
+/// holds the root. Implement this to back a tree with something other
+/// than memory, e.g. a disk-backed key-value store, for trees too large
+/// to hold in RAM.
+pub trait NodeStore {
+    fn get(&self, level: u32, index: usize) -> Option<Vec<u8>>;
+    fn put(&mut self, level: u32, index: usize, hash: Vec<u8>);
+    /// Drops the node at `(level, index)`, if present, returning whether
+    /// anything was removed.
+    fn remove(&mut self, level: u32, index: usize) -> bool;
+}
+
+/// The default in-memory `NodeStore`, backed by a hash map.
+#[derive(Clone, Debug, Default)]
+pub struct InMemoryNodeStore {
+    nodes: HashMap<(u32, usize), Vec<u8>>,
+}
+
+impl InMemoryNodeStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl NodeStore for InMemoryNodeStore {
+    fn get(&self, level: u32, index: usize) -> Option<Vec<u8>> {
+        self.nodes.get(&(level, index)).cloned()
+    }
+
+    fn put(&mut self, level: u32, index: usize, hash: Vec<u8>) {
+        self.nodes.insert((level, index), hash);
+    }
+
+    fn remove(&mut self, level: u32, index: usize) -> bool {
+        self.nodes.remove(&(level, index)).is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_put_round_trip() {
+        let mut store = InMemoryNodeStore::new();
+        assert_eq!(store.get(0, 0), None);
+
+        store.put(0, 0, vec![1, 2, 3]);
+        assert_eq!(store.get(0, 0), Some(vec![1, 2, 3]));
+    }
+
+    #[test]
+    fn test_remove() {
+        let mut store = InMemoryNodeStore::new();
+        store.put(1, 2, vec![4, 5, 6]);
+
+        assert!(store.remove(1, 2));
+        assert_eq!(store.get(1, 2), None);
+        assert!(!store.remove(1, 2));
+    }
+}
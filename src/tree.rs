@@ -1,31 +1,78 @@
 use sha2::Digest;
 use std::marker::PhantomData;
 
+use crate::encoding;
 use crate::errors::MerkleError;
+use crate::multiproof::MultiProof;
 use crate::proof::Proof;
-
+use crate::pruner::MerkleTreePruner;
+use crate::store::{InMemoryNodeStore, NodeStore};
+
+/// Domain-separation tag prepended to leaf data before hashing, so that a
+/// leaf can never be mistaken for the concatenation of two internal nodes
+/// (the classic Merkle second-preimage attack).
+pub const LEAF_PREFIX: u8 = 0x00;
+/// Domain-separation tag prepended to the concatenation of two child hashes
+/// before hashing an internal node.
+pub const NODE_PREFIX: u8 = 0x01;
+
+/// A right-sparse Merkle tree only materializes nodes on the path to an
+/// occupied leaf; any subtree that is entirely unoccupied is represented
+/// implicitly by the precomputed `zero_hashes` table rather than being
+/// allocated and hashed. This lets `HEIGHT` be large (e.g. 32, for
+/// account-style trees) without a `2^HEIGHT` memory blowup.
+///
+/// Internal nodes are kept behind the `NodeStore` trait rather than a bare
+/// `Vec`, so a tree too large for RAM can be backed by a disk-resident
+/// store instead; `from_data` builds over an `InMemoryNodeStore` for the
+/// common case, and `from_data_with_store` takes any other store.
 #[derive(Clone, Debug)]
-pub struct MerkleTree<H: Digest, const HEIGHT: u32> {
+pub struct MerkleTree<H: Digest, S: NodeStore, const HEIGHT: u32> {
     leaves: Vec<Vec<u8>>,
-    tree: Vec<Vec<u8>>,
+    store: S,
+    // zero_hashes[level] is the hash of an entirely empty subtree of that
+    // height, used as the implicit sibling whenever one isn't materialized.
+    zero_hashes: Vec<Vec<u8>>,
     hasher: PhantomData<H>,
 }
 
-impl<H: Digest, const HEIGHT: u32> MerkleTree<H, HEIGHT> {
+impl<H: Digest, const HEIGHT: u32> MerkleTree<H, InMemoryNodeStore, HEIGHT> {
     pub fn from_data(leaves: Vec<Vec<u8>>) -> Result<Self, MerkleError> {
+        Self::from_data_with_store(leaves, InMemoryNodeStore::new())
+    }
+}
+
+impl<H: Digest, S: NodeStore, const HEIGHT: u32> MerkleTree<H, S, HEIGHT> {
+    pub fn from_data_with_store(leaves: Vec<Vec<u8>>, mut store: S) -> Result<Self, MerkleError> {
         // Check leaves don't exceed capacity
         if leaves.len() > 2_usize.pow(HEIGHT) {
             return Err(MerkleError::InsufficientHeight(leaves.len()));
         }
 
+        // An empty leaf is reserved to mean "unoccupied" (see zero_hashes),
+        // so real data can never collide with the padding sentinel
+        if leaves.iter().any(|leaf| leaf.is_empty()) {
+            return Err(MerkleError::InvalidLeaf());
+        }
+
+        let zero_hashes = Self::build_zero_hashes();
+        Self::build_tree(&leaves, &zero_hashes, &mut store);
+
         Ok(Self {
-            leaves: leaves.clone(),
-            tree: Self::build_tree(leaves),
+            leaves,
+            store,
+            zero_hashes,
             hasher: PhantomData,
         })
     }
 
     pub fn insert(&mut self, value: &[u8]) -> Result<(), MerkleError> {
+        // An empty leaf is reserved to mean "unoccupied" (see zero_hashes),
+        // so real data can never collide with the padding sentinel
+        if value.is_empty() {
+            return Err(MerkleError::InvalidLeaf());
+        }
+
         // Check whether tree is full
         if self.leaves.len() == 2_usize.pow(HEIGHT) {
             return Err(MerkleError::MerkleTreeFull());
@@ -36,24 +83,108 @@ impl<H: Digest, const HEIGHT: u32> MerkleTree<H, HEIGHT> {
 
         // insert the value hash in the first level of the tree
         let mut index = self.leaves.len() - 1;
-        self.tree[index] = Self::hash(value);
+        self.store.put(0, index, Self::hash(value));
+
+        // change only the appropriate node hashes on the path to the root,
+        // combining with a cached zero hash whenever a sibling subtree is
+        // entirely empty, and rebuilding from the retained leaf data
+        // whenever a sibling was materialized once but has since been
+        // dropped by `prune`
+        for level in 0..HEIGHT {
+            let parent_index = index / 2;
+            let left = self.node_hash(level, parent_index * 2);
+            let right = self.node_hash(level, parent_index * 2 + 1);
+            let parent_hash = Self::hash_pair(&left, &right);
+
+            self.store.put(level + 1, parent_index, parent_hash);
+            index = parent_index;
+        }
 
-        let mut offset = 0;
+        Ok(())
+    }
 
-        // change only the appropriate node hashes within the tree
-        for i in 0..HEIGHT {
-            let current_hash = if index % 2 == 0 {
-                Self::hash_pair(&self.tree[offset + index], &self.tree[offset + index + 1])
-            } else {
-                Self::hash_pair(&self.tree[offset + index - 1], &self.tree[offset + index])
-            };
+    /// Returns the hash of the node at `(level, index)`, rebuilding it from
+    /// `leaves` if `prune` has since dropped it from the store. A node past
+    /// the last real leaf is entirely unoccupied, so it's resolved to the
+    /// cached zero hash instead of recursing down an empty subtree. Also
+    /// caches the rebuilt hash back into the store, since a caller on the
+    /// hot insert path will likely need the same ancestor again.
+    fn node_hash(&mut self, level: u32, index: usize) -> Vec<u8> {
+        if let Some(hash) = self.store.get(level, index) {
+            return hash;
+        }
 
-            index = index / 2;
-            offset = offset + 2_usize.pow(HEIGHT - i);
-            self.tree[offset + index] = current_hash;
+        let hash = self.resolve_node(level, index);
+        self.store.put(level, index, hash.clone());
+        hash
+    }
+
+    /// Read-only counterpart to `node_hash`, for callers (the proof paths)
+    /// that must not mutate the store: rebuilds a pruned-but-occupied
+    /// node's hash from `leaves` on the fly instead of caching it.
+    fn resolve_node(&self, level: u32, index: usize) -> Vec<u8> {
+        if let Some(hash) = self.store.get(level, index) {
+            return hash;
         }
 
-        Ok(())
+        if index * 2_usize.pow(level) >= self.leaves.len() {
+            return self.zero_hashes[level as usize].clone();
+        }
+
+        if level == 0 {
+            Self::hash(&self.leaves[index])
+        } else {
+            let left = self.resolve_node(level - 1, index * 2);
+            let right = self.resolve_node(level - 1, index * 2 + 1);
+            Self::hash_pair(&left, &right)
+        }
+    }
+
+    /// Pushes a batch of leaves, recomputing each newly affected path once,
+    /// and returns the resulting root. Fails atomically: if any leaf is
+    /// invalid or the batch would overflow the tree, no leaf is inserted.
+    pub fn extend(&mut self, values: &[&[u8]]) -> Result<Vec<u8>, MerkleError> {
+        if values.iter().any(|value| value.is_empty()) {
+            return Err(MerkleError::InvalidLeaf());
+        }
+
+        if self.leaves.len() + values.len() > 2_usize.pow(HEIGHT) {
+            return Err(MerkleError::MerkleTreeFull());
+        }
+
+        if values.is_empty() {
+            return Ok(self.root().expect("root always exists"));
+        }
+
+        let start_index = self.leaves.len();
+        for value in values {
+            self.leaves.push(value.to_vec());
+        }
+        for (offset, value) in values.iter().enumerate() {
+            self.store.put(0, start_index + offset, Self::hash(value));
+        }
+
+        // Several new leaves can share the same ancestor; collapse the
+        // batch down to the distinct parent indices at each level so a
+        // shared ancestor is recomputed once instead of once per leaf.
+        let mut touched: Vec<usize> = (start_index..start_index + values.len())
+            .map(|index| index / 2)
+            .collect();
+        touched.dedup();
+
+        for level in 0..HEIGHT {
+            for &parent_index in &touched {
+                let left = self.node_hash(level, parent_index * 2);
+                let right = self.node_hash(level, parent_index * 2 + 1);
+                self.store
+                    .put(level + 1, parent_index, Self::hash_pair(&left, &right));
+            }
+
+            touched = touched.iter().map(|&index| index / 2).collect();
+            touched.dedup();
+        }
+
+        Ok(self.root().expect("root always exists"))
     }
 
     pub fn get_proof(&self, mut index: usize) -> Result<Proof<H>, MerkleError> {
@@ -63,76 +194,163 @@ impl<H: Digest, const HEIGHT: u32> MerkleTree<H, HEIGHT> {
 
         let mut lemma = Vec::with_capacity(HEIGHT as usize);
         let mut path = Vec::with_capacity(HEIGHT as usize);
-        let mut offset = 0;
 
-        for i in 0..HEIGHT {
+        for level in 0..HEIGHT {
             // Sibling node and position: right --> true, left --> false
-            let (sibling_hash, pos) = if index % 2 == 0 {
-                (self.tree[offset + index + 1].clone(), true)
+            let (sibling_index, pos) = if index % 2 == 0 {
+                (index + 1, true)
             } else {
-                (self.tree[offset + index - 1].clone(), false)
+                (index - 1, false)
             };
 
+            // A sibling that wasn't materialized is either an entirely
+            // empty subtree (resolved to the cached zero hash) or a real,
+            // still-occupied leaf that `prune` has since dropped from the
+            // store (rebuilt from `leaves`).
+            let sibling_hash = self.resolve_node(level, sibling_index);
+
             lemma.push(sibling_hash);
             path.push(pos);
 
             index = index / 2;
-            offset = offset + 2_usize.pow(HEIGHT - i);
         }
 
         Ok(Proof::<H>::new(lemma, path))
     }
 
-    pub fn root(&self) -> Option<&[u8]> {
-        self.tree.last().map(AsRef::as_ref)
+    /// Builds a proof that every leaf in `indices` belongs to this tree,
+    /// sharing authentication nodes between them instead of concatenating
+    /// one single-leaf proof per index.
+    pub fn get_multiproof(&self, indices: &[usize]) -> Result<MultiProof<H, HEIGHT>, MerkleError> {
+        for &index in indices {
+            if index >= self.leaves.len() {
+                return Err(MerkleError::IndexOutOfBounds(index));
+            }
+        }
+
+        let mut sorted_indices = indices.to_vec();
+        sorted_indices.sort_unstable();
+        sorted_indices.dedup();
+
+        let mut siblings = Vec::new();
+        let mut current = sorted_indices.clone();
+
+        for level in 0..HEIGHT {
+            let mut next = Vec::with_capacity(current.len());
+            let mut i = 0;
+
+            while i < current.len() {
+                let index = current[i];
+                let sibling_index = index ^ 1;
+
+                if i + 1 < current.len() && current[i + 1] == sibling_index {
+                    // The sibling is itself one of the covered leaves, so
+                    // the verifier can derive this parent without help.
+                    i += 2;
+                } else {
+                    // Resolves to the cached zero hash for a genuinely
+                    // empty subtree, or rebuilds from `leaves` if `prune`
+                    // dropped a still-occupied sibling's node.
+                    let sibling_hash = self.resolve_node(level, sibling_index);
+                    siblings.push(sibling_hash);
+                    i += 1;
+                }
+
+                next.push(index / 2);
+            }
+
+            current = next;
+        }
+
+        Ok(MultiProof::new(sorted_indices, siblings))
+    }
+
+    pub fn root(&self) -> Option<Vec<u8>> {
+        Some(
+            self.store
+                .get(HEIGHT, 0)
+                .unwrap_or_else(|| self.zero_hashes[HEIGHT as usize].clone()),
+        )
     }
 
     pub fn get_value(&self, index: usize) -> Option<&[u8]> {
         self.leaves.get(index).map(AsRef::as_ref)
     }
 
+    /// Hex-encoded root, for transmitting it as text. Decode back to bytes
+    /// with `encoding::decode_hex`.
+    pub fn root_hex(&self) -> Option<String> {
+        self.root().map(|root| encoding::encode_hex(&root))
+    }
+
+    /// Base64-encoded root, for transmitting it as text. Decode back to
+    /// bytes with `encoding::decode_base64`.
+    pub fn root_base64(&self) -> Option<String> {
+        self.root().map(|root| encoding::encode_base64(&root))
+    }
+
+    /// Drops stored node hashes (leaf hashes included) that only served leaves at or beyond
+    /// `retained_leaf_count`, returning how many nodes were removed.
+    /// Leaves themselves, and proofs for any leaf below the retained
+    /// count, are unaffected.
+    pub fn prune(&mut self, retained_leaf_count: usize) -> usize {
+        MerkleTreePruner::prune(&mut self.store, HEIGHT, self.leaves.len(), retained_leaf_count)
+    }
+
     fn hash(data: &[u8]) -> Vec<u8> {
-        H::digest(data).to_vec()
+        let mut hasher = H::new();
+        hasher.update([LEAF_PREFIX]);
+        hasher.update(data);
+        hasher.finalize().to_vec()
     }
 
     fn hash_pair(left: &[u8], right: &[u8]) -> Vec<u8> {
         let mut hasher = H::new();
+        hasher.update([NODE_PREFIX]);
         hasher.update(left);
         hasher.update(right);
         hasher.finalize().to_vec()
     }
 
-    fn build_tree(leaves: Vec<Vec<u8>>) -> Vec<Vec<u8>> {
-        let mut tree = Vec::with_capacity(2_usize.pow(HEIGHT + 1));
+    // Precomputes the hash of an empty subtree at every level, from the
+    // hash of an empty leaf up to the hash of an entirely empty tree of
+    // height HEIGHT: zero[0] = H(leaf_prefix || empty), zero[i] =
+    // H(node_prefix || zero[i - 1] || zero[i - 1]).
+    fn build_zero_hashes() -> Vec<Vec<u8>> {
+        let mut zero_hashes = Vec::with_capacity(HEIGHT as usize + 1);
+        zero_hashes.push(Self::hash(&[]));
+
+        for level in 1..=HEIGHT as usize {
+            let previous = &zero_hashes[level - 1];
+            zero_hashes.push(Self::hash_pair(previous, previous));
+        }
 
-        // Compute leaf hashes
-        let mut hashes = leaves
-            .iter()
-            .map(|d| Self::hash(d))
-            .collect::<Vec<Vec<u8>>>();
-        println!("{:x?}", hashes);
-        // Pad the hashed leaves with zero hashes
-        hashes.extend(vec![
-            H::digest(b"0").to_vec();
-            2_usize.pow(HEIGHT) - leaves.len()
-        ]);
-        println!("{:x?}", hashes);
+        zero_hashes
+    }
 
-        tree.extend(hashes.clone());
+    // Populates the store with only the occupied portion of the tree: level
+    // 0 holds one hash per real leaf (no padding), and each subsequent
+    // level holds only the nodes derivable from at least one real leaf,
+    // combining with the matching zero hash whenever a sibling subtree is
+    // unoccupied.
+    fn build_tree(leaves: &[Vec<u8>], zero_hashes: &[Vec<u8>], store: &mut S) {
+        let mut previous = leaves.iter().map(|d| Self::hash(d)).collect::<Vec<_>>();
+        for (index, hash) in previous.iter().enumerate() {
+            store.put(0, index, hash.clone());
+        }
 
-        for h in 0..HEIGHT {
-            let mut next_layer = Vec::with_capacity(2_usize.pow(HEIGHT - h - 1));
+        for level in 0..HEIGHT {
+            let mut next_layer = Vec::with_capacity(previous.len().div_ceil(2));
 
-            for i in (0..hashes.len()).step_by(2) {
-                let hash_pair_result = Self::hash_pair(&hashes[i], &hashes[i + 1]);
-                next_layer.push(hash_pair_result);
+            for i in (0..previous.len()).step_by(2) {
+                let right = previous.get(i + 1).unwrap_or(&zero_hashes[level as usize]);
+                let hash = Self::hash_pair(&previous[i], right);
+                store.put(level + 1, i / 2, hash.clone());
+                next_layer.push(hash);
             }
 
-            tree.extend(next_layer.clone());
-            hashes = next_layer;
+            previous = next_layer;
         }
-
-        tree
     }
 }
 
@@ -150,7 +368,10 @@ mod tests {
     #[test]
     fn test_from_data() {
         let leaves = sample_leaves();
-        let tree = MerkleTree::<Sha256, TEST_TREE_HEIGHT>::from_data(leaves.clone()).unwrap();
+        let tree = MerkleTree::<Sha256, InMemoryNodeStore, TEST_TREE_HEIGHT>::from_data(
+            leaves.clone(),
+        )
+        .unwrap();
         assert_eq!(tree.leaves, leaves);
     }
 
@@ -158,14 +379,17 @@ mod tests {
     fn test_from_data_insufficient_height() {
         let mut leaves = sample_leaves();
         leaves.extend(vec![b"extra".to_vec(), b"extra".to_vec()]);
-        let result = MerkleTree::<Sha256, TEST_TREE_HEIGHT>::from_data(leaves);
+        let result = MerkleTree::<Sha256, InMemoryNodeStore, TEST_TREE_HEIGHT>::from_data(leaves);
         assert!(matches!(result, Err(MerkleError::InsufficientHeight(_))));
     }
 
     #[test]
     fn test_insert() {
         let leaves = sample_leaves();
-        let mut tree = MerkleTree::<Sha256, TEST_TREE_HEIGHT>::from_data(leaves.clone()).unwrap();
+        let mut tree = MerkleTree::<Sha256, InMemoryNodeStore, TEST_TREE_HEIGHT>::from_data(
+            leaves.clone(),
+        )
+        .unwrap();
         assert!(tree.insert(b"extra").is_ok());
         assert_eq!(tree.leaves.len(), 4);
     }
@@ -173,27 +397,365 @@ mod tests {
     #[test]
     fn test_insert_tree_full() {
         let leaves = vec![vec![0u8; 32]; 2_usize.pow(TEST_TREE_HEIGHT)];
-        let mut tree = MerkleTree::<Sha256, TEST_TREE_HEIGHT>::from_data(leaves).unwrap();
+        let mut tree =
+            MerkleTree::<Sha256, InMemoryNodeStore, TEST_TREE_HEIGHT>::from_data(leaves).unwrap();
         assert!(matches!(
             tree.insert(b"extra"),
             Err(MerkleError::MerkleTreeFull())
         ));
     }
 
+    #[test]
+    fn test_from_data_rejects_empty_leaf() {
+        let leaves = vec![b"apple".to_vec(), vec![]];
+        let result = MerkleTree::<Sha256, InMemoryNodeStore, TEST_TREE_HEIGHT>::from_data(leaves);
+        assert!(matches!(result, Err(MerkleError::InvalidLeaf())));
+    }
+
+    #[test]
+    fn test_insert_rejects_empty_leaf() {
+        let mut tree =
+            MerkleTree::<Sha256, InMemoryNodeStore, TEST_TREE_HEIGHT>::from_data(sample_leaves())
+                .unwrap();
+        assert!(matches!(tree.insert(b""), Err(MerkleError::InvalidLeaf())));
+    }
+
+    #[test]
+    fn test_extend_adds_all_leaves_and_returns_root() {
+        let mut tree =
+            MerkleTree::<Sha256, InMemoryNodeStore, TEST_TREE_HEIGHT>::from_data(vec![]).unwrap();
+        let root = tree.extend(&[b"apple", b"banana", b"cherry"]).unwrap();
+
+        assert_eq!(tree.leaves.len(), 3);
+        assert_eq!(tree.root(), Some(root));
+    }
+
+    #[test]
+    fn test_extend_rejects_when_over_capacity() {
+        let mut tree =
+            MerkleTree::<Sha256, InMemoryNodeStore, TEST_TREE_HEIGHT>::from_data(sample_leaves())
+                .unwrap();
+        assert!(matches!(
+            tree.extend(&[b"extra1", b"extra2"]),
+            Err(MerkleError::MerkleTreeFull())
+        ));
+        // The failed batch must not have partially inserted any leaf.
+        assert_eq!(tree.leaves.len(), 3);
+    }
+
+    #[test]
+    fn test_extend_rejects_empty_leaf() {
+        let mut tree =
+            MerkleTree::<Sha256, InMemoryNodeStore, TEST_TREE_HEIGHT>::from_data(vec![]).unwrap();
+        assert!(matches!(
+            tree.extend(&[b"apple", b""]),
+            Err(MerkleError::InvalidLeaf())
+        ));
+        assert!(tree.leaves.is_empty());
+    }
+
     #[test]
     fn test_get_root() {
         let leaves = sample_leaves();
-        let tree = MerkleTree::<Sha256, TEST_TREE_HEIGHT>::from_data(leaves).unwrap();
+        let tree =
+            MerkleTree::<Sha256, InMemoryNodeStore, TEST_TREE_HEIGHT>::from_data(leaves).unwrap();
         assert!(tree.root().is_some());
     }
 
+    #[test]
+    fn test_root_hex_and_base64_round_trip() {
+        let leaves = sample_leaves();
+        let tree =
+            MerkleTree::<Sha256, InMemoryNodeStore, TEST_TREE_HEIGHT>::from_data(leaves).unwrap();
+        let root = tree.root().unwrap();
+
+        assert_eq!(
+            encoding::decode_hex(&tree.root_hex().unwrap()).unwrap(),
+            root
+        );
+        assert_eq!(
+            encoding::decode_base64(&tree.root_base64().unwrap()).unwrap(),
+            root
+        );
+    }
+
+    #[test]
+    fn test_tall_sparse_tree_with_few_leaves() {
+        // HEIGHT = 32 would allocate 2^32 nodes under the old full-tree
+        // representation; the sparse layout only materializes the path to
+        // the single occupied leaf.
+        const TALL_HEIGHT: u32 = 32;
+        let tree = MerkleTree::<Sha256, InMemoryNodeStore, TALL_HEIGHT>::from_data(vec![
+            b"apple".to_vec()
+        ])
+        .unwrap();
+
+        assert!(tree.root().is_some());
+        let proof = tree.get_proof(0).unwrap();
+        assert_eq!(proof.lemma.len(), TALL_HEIGHT as usize);
+        assert!(proof.verify(&tree.root().unwrap(), b"apple"));
+    }
+
+    #[test]
+    fn test_proof_against_empty_sibling_subtree() {
+        // A single leaf's sibling subtrees are entirely empty at every
+        // level, so the lemma should be built purely from zero hashes.
+        let tree =
+            MerkleTree::<Sha256, InMemoryNodeStore, TEST_TREE_HEIGHT>::from_data(vec![
+                b"apple".to_vec(),
+            ])
+            .unwrap();
+        let zero_hashes = MerkleTree::<Sha256, InMemoryNodeStore, TEST_TREE_HEIGHT>::build_zero_hashes();
+
+        let proof = tree.get_proof(0).unwrap();
+        assert_eq!(
+            proof.lemma,
+            zero_hashes[..TEST_TREE_HEIGHT as usize].to_vec()
+        );
+        assert!(proof.verify(&tree.root().unwrap(), b"apple"));
+    }
+
+    #[test]
+    fn test_empty_tree_root_is_zero_hash() {
+        let tree =
+            MerkleTree::<Sha256, InMemoryNodeStore, TEST_TREE_HEIGHT>::from_data(vec![]).unwrap();
+        let zero_hashes = MerkleTree::<Sha256, InMemoryNodeStore, TEST_TREE_HEIGHT>::build_zero_hashes();
+        assert_eq!(tree.root(), Some(zero_hashes[TEST_TREE_HEIGHT as usize].clone()));
+    }
+
+    #[test]
+    fn test_domain_separation_rejects_second_preimage() {
+        // Craft a 64-byte "leaf" that is exactly the concatenation of two
+        // sibling hashes, as an attacker would to forge an internal node as
+        // a leaf preimage. Without domain separation this leaf's hash would
+        // equal the node's hash; with it, they must differ.
+        type Tree = MerkleTree<Sha256, InMemoryNodeStore, TEST_TREE_HEIGHT>;
+        let left = Tree::hash(b"apple");
+        let right = Tree::hash(b"banana");
+
+        let node_hash = Tree::hash_pair(&left, &right);
+
+        let mut forged_leaf = left.clone();
+        forged_leaf.extend(right);
+        assert_eq!(forged_leaf.len(), 64);
+        let forged_leaf_hash = Tree::hash(&forged_leaf);
+
+        assert_ne!(forged_leaf_hash, node_hash);
+    }
+
     #[test]
     fn test_get_value() {
         let leaves = sample_leaves();
-        let tree = MerkleTree::<Sha256, TEST_TREE_HEIGHT>::from_data(leaves.clone()).unwrap();
+        let tree = MerkleTree::<Sha256, InMemoryNodeStore, TEST_TREE_HEIGHT>::from_data(
+            leaves.clone(),
+        )
+        .unwrap();
         assert_eq!(tree.get_value(0), Some(leaves[0].as_ref()));
         assert_eq!(tree.get_value(1), Some(leaves[1].as_ref()));
         assert_eq!(tree.get_value(2), Some(leaves[2].as_ref()));
         assert_eq!(tree.get_value(3), None);
     }
+
+    #[test]
+    fn test_prune_keeps_roots_and_proofs_for_retained_leaves() {
+        const HEIGHT: u32 = 3;
+        let leaves: Vec<Vec<u8>> = (0..8u8).map(|i| vec![i]).collect();
+        let mut tree =
+            MerkleTree::<Sha256, InMemoryNodeStore, HEIGHT>::from_data(leaves.clone()).unwrap();
+        let root_before = tree.root().unwrap();
+        let proof_before = tree.get_proof(1).unwrap();
+
+        let removed = tree.prune(2);
+        assert!(removed > 0);
+
+        assert_eq!(tree.root(), Some(root_before));
+        let proof_after = tree.get_proof(1).unwrap();
+        assert_eq!(proof_after.lemma, proof_before.lemma);
+        assert!(proof_after.verify(&tree.root().unwrap(), &leaves[1]));
+    }
+
+    #[test]
+    fn test_prune_zero_retains_current_root() {
+        const HEIGHT: u32 = 3;
+        let leaves: Vec<Vec<u8>> = (0..8u8).map(|i| vec![i]).collect();
+        let mut tree =
+            MerkleTree::<Sha256, InMemoryNodeStore, HEIGHT>::from_data(leaves).unwrap();
+        let root_before = tree.root().unwrap();
+
+        tree.prune(0);
+
+        assert_eq!(tree.root(), Some(root_before));
+    }
+
+    #[test]
+    fn test_insert_after_prune_rebuilds_pruned_siblings() {
+        // prune(2) on 5 leaves drops the level-0 hash for leaf 4 (the
+        // sibling of the still-unoccupied leaf 5), even though leaf 4 is
+        // still logically present. Inserting past it must rebuild that
+        // hash from the retained leaf data rather than panicking or
+        // silently treating it as empty.
+        const HEIGHT: u32 = 3;
+        let leaves: Vec<Vec<u8>> = (0..5u8).map(|i| vec![i]).collect();
+        let mut tree =
+            MerkleTree::<Sha256, InMemoryNodeStore, HEIGHT>::from_data(leaves.clone()).unwrap();
+
+        tree.prune(2);
+        assert!(tree.insert(b"newleaf").is_ok());
+
+        let rebuilt_root = tree.root().unwrap();
+
+        let mut fresh_leaves = leaves;
+        fresh_leaves.push(b"newleaf".to_vec());
+        let fresh_tree =
+            MerkleTree::<Sha256, InMemoryNodeStore, HEIGHT>::from_data(fresh_leaves).unwrap();
+
+        assert_eq!(rebuilt_root, fresh_tree.root().unwrap());
+
+        let proof = tree.get_proof(4).unwrap();
+        assert!(proof.verify(&rebuilt_root, &[4]));
+    }
+
+    #[test]
+    fn test_extend_after_prune_rebuilds_pruned_siblings() {
+        const HEIGHT: u32 = 3;
+        let leaves: Vec<Vec<u8>> = (0..5u8).map(|i| vec![i]).collect();
+        let mut tree =
+            MerkleTree::<Sha256, InMemoryNodeStore, HEIGHT>::from_data(leaves.clone()).unwrap();
+
+        tree.prune(2);
+        let root = tree.extend(&[b"f", b"g", b"h"]).unwrap();
+
+        assert_eq!(tree.leaves.len(), 8);
+        assert_eq!(tree.root(), Some(root));
+    }
+
+    #[test]
+    fn test_get_proof_after_prune_rebuilds_pruned_sibling() {
+        // prune(5) on 8 leaves drops the level-0 slots for leaves 6 and 7,
+        // though both are still real leaves; leaf 7's hash is the sibling
+        // needed to prove leaf 6.
+        const HEIGHT: u32 = 3;
+        let leaves: Vec<Vec<u8>> = (0..8u8).map(|i| vec![i]).collect();
+        let mut tree =
+            MerkleTree::<Sha256, InMemoryNodeStore, HEIGHT>::from_data(leaves.clone()).unwrap();
+
+        tree.prune(5);
+        assert_eq!(tree.get_value(7), Some(leaves[7].as_ref()));
+
+        let proof = tree.get_proof(6).unwrap();
+        assert!(proof.verify(&tree.root().unwrap(), &leaves[6]));
+    }
+
+    #[test]
+    fn test_get_multiproof_after_prune_rebuilds_pruned_sibling() {
+        const HEIGHT: u32 = 3;
+        let leaves: Vec<Vec<u8>> = (0..8u8).map(|i| vec![i]).collect();
+        let mut tree =
+            MerkleTree::<Sha256, InMemoryNodeStore, HEIGHT>::from_data(leaves.clone()).unwrap();
+
+        tree.prune(5);
+
+        let multiproof = tree.get_multiproof(&[6]).unwrap();
+        assert!(multiproof.verify(&tree.root().unwrap(), &[(6, leaves[6].as_ref())]));
+    }
+
+    #[test]
+    fn test_extend_recomputes_each_shared_ancestor_once() {
+        use std::cell::RefCell;
+        use std::collections::HashMap;
+
+        // A store that counts how many times each node is written, so a
+        // batch that shares ancestors between leaves can be checked for
+        // recomputing each one once rather than once per leaf.
+        #[derive(Default)]
+        struct CountingStore {
+            nodes: InMemoryNodeStore,
+            put_counts: RefCell<HashMap<(u32, usize), usize>>,
+        }
+
+        impl NodeStore for CountingStore {
+            fn get(&self, level: u32, index: usize) -> Option<Vec<u8>> {
+                self.nodes.get(level, index)
+            }
+
+            fn put(&mut self, level: u32, index: usize, hash: Vec<u8>) {
+                *self.put_counts.borrow_mut().entry((level, index)).or_insert(0) += 1;
+                self.nodes.put(level, index, hash);
+            }
+
+            fn remove(&mut self, level: u32, index: usize) -> bool {
+                self.nodes.remove(level, index)
+            }
+        }
+
+        const HEIGHT: u32 = 3;
+        let mut tree = MerkleTree::<Sha256, CountingStore, HEIGHT>::from_data_with_store(
+            vec![],
+            CountingStore::default(),
+        )
+        .unwrap();
+
+        // All four leaves land under the same level-2 ancestor (index 0),
+        // and pair up under two distinct level-1 parents (indices 0, 1).
+        tree.extend(&[b"a", b"b", b"c", b"d"]).unwrap();
+
+        let counts = tree.store.put_counts.borrow();
+        assert_eq!(counts.get(&(1, 0)), Some(&1));
+        assert_eq!(counts.get(&(1, 1)), Some(&1));
+        assert_eq!(counts.get(&(2, 0)), Some(&1));
+    }
+
+    #[test]
+    fn test_tree_backed_by_mock_store_matches_in_memory() {
+        use std::cell::RefCell;
+        use std::collections::HashMap;
+
+        // A store that forwards to a HashMap, standing in for a remote or
+        // disk-backed implementation plugged in by a caller.
+        #[derive(Default)]
+        struct MockStore {
+            nodes: RefCell<HashMap<(u32, usize), Vec<u8>>>,
+        }
+
+        impl NodeStore for MockStore {
+            fn get(&self, level: u32, index: usize) -> Option<Vec<u8>> {
+                self.nodes.borrow().get(&(level, index)).cloned()
+            }
+
+            fn put(&mut self, level: u32, index: usize, hash: Vec<u8>) {
+                self.nodes.borrow_mut().insert((level, index), hash);
+            }
+
+            fn remove(&mut self, level: u32, index: usize) -> bool {
+                self.nodes.borrow_mut().remove(&(level, index)).is_some()
+            }
+        }
+
+        const HEIGHT: u32 = 3;
+        let leaves = sample_leaves();
+
+        let in_memory =
+            MerkleTree::<Sha256, InMemoryNodeStore, HEIGHT>::from_data(leaves.clone()).unwrap();
+        let mut mock =
+            MerkleTree::<Sha256, MockStore, HEIGHT>::from_data_with_store(
+                leaves.clone(),
+                MockStore::default(),
+            )
+            .unwrap();
+
+        assert_eq!(in_memory.root(), mock.root());
+        for index in 0..leaves.len() {
+            assert_eq!(
+                in_memory.get_proof(index).unwrap().lemma,
+                mock.get_proof(index).unwrap().lemma
+            );
+        }
+
+        mock.prune(1);
+        assert_eq!(in_memory.root(), mock.root());
+        assert!(mock
+            .get_proof(0)
+            .unwrap()
+            .verify(&mock.root().unwrap(), &leaves[0]));
+    }
 }